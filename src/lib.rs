@@ -1,26 +1,23 @@
 use serde::Serialize;
-use std::str::FromStr;
 
-use sysctl::Ctl;
-use sysctl::Sysctl; // Import Ctl directly
+mod measure;
+mod source;
+
+use source::{CpuInfoSource, CpuUsageSource};
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+type CurrentCpuInfoSource = source::darwin::DarwinCpuInfoSource;
+
+#[cfg(target_os = "linux")]
+type CurrentCpuInfoSource = source::linux::LinuxCpuInfoSource;
+
+#[cfg(target_os = "windows")]
+type CurrentCpuInfoSource = source::windows::WindowsCpuInfoSource;
 
 // Scalar for representing large integer values (64-bit).
 // Maps to standard `i64` in Rust.
 type Long = i64;
 
-/// Generic helper to get a sysctl value.
-/// Returns a default value if the key is not found, cannot be read, or fails to parse.
-fn get_sysctl_value<T>(key: &str) -> T
-where
-    T: FromStr + Default,
-{
-    Ctl::new(key)
-        .ok()
-        .and_then(|ctl| ctl.value_string().ok())
-        .and_then(|s| s.parse::<T>().ok())
-        .unwrap_or_default()
-}
-
 /// Represents the overall CPU information, acting as a container.
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
@@ -38,14 +35,16 @@ impl Default for CPUInfo {
 }
 
 impl CPUInfo {
-    /// Creates a new CPUInfo instance by fetching data from sysctl.
+    /// Creates a new CPUInfo instance by fetching data from the current
+    /// platform's [`CpuInfoSource`] backend (sysctl on macOS/BSD, `/proc`
+    /// and `/sys` on Linux, or the Win32 APIs on Windows).
     pub fn new() -> Self {
         // No Result
         CPUInfo {
-            identification: CPUIdentification::from_sysctl(),
-            core_counts: CPUCoreCounts::from_sysctl(),
-            frequency: CPUFrequency::from_sysctl(),
-            performance_levels: PerformanceLevel::all_from_sysctl(),
+            identification: CurrentCpuInfoSource::identification(),
+            core_counts: CurrentCpuInfoSource::core_counts(),
+            frequency: CurrentCpuInfoSource::frequency(),
+            performance_levels: CurrentCpuInfoSource::performance_levels(),
         }
     }
 }
@@ -60,17 +59,287 @@ pub struct CPUIdentification {
     pub vendor: String,
     /// sysctl: machdep.cpu.feature_bits
     pub feature_bits: String,
+    /// Named ISA extensions decoded from `feature_bits` (see [`CpuFeatures`]).
+    pub features: CpuFeatures,
 }
 
-impl CPUIdentification {
-    /// Creates a new CPUIdentification instance by fetching data from sysctl.
-    pub fn from_sysctl() -> Self {
-        CPUIdentification {
-            brand_string: get_sysctl_value("machdep.cpu.brand_string"),
-            vendor: get_sysctl_value("machdep.cpu.vendor"),
-            feature_bits: get_sysctl_value("machdep.cpu.feature_bits"),
+/// A single named ISA extension, as reported by `machdep.cpu.feature_bits`
+/// or the `machdep.cpu.features`/`machdep.cpu.leaf7_features` name lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::Enum))]
+pub enum CpuFeature {
+    Fpu,
+    Mmx,
+    Sse,
+    Sse2,
+    Sse3,
+    Pclmulqdq,
+    Ssse3,
+    Fma,
+    Sse41,
+    Sse42,
+    Popcnt,
+    Aes,
+    Avx,
+    Rdrand,
+    Avx2,
+    Bmi1,
+    Bmi2,
+    Rdseed,
+    Adx,
+    Sha,
+    Avx512f,
+}
+
+impl CpuFeature {
+    /// All features this crate knows how to decode, in a stable order.
+    fn all() -> &'static [CpuFeature] {
+        &[
+            CpuFeature::Fpu,
+            CpuFeature::Mmx,
+            CpuFeature::Sse,
+            CpuFeature::Sse2,
+            CpuFeature::Sse3,
+            CpuFeature::Pclmulqdq,
+            CpuFeature::Ssse3,
+            CpuFeature::Fma,
+            CpuFeature::Sse41,
+            CpuFeature::Sse42,
+            CpuFeature::Popcnt,
+            CpuFeature::Aes,
+            CpuFeature::Avx,
+            CpuFeature::Rdrand,
+            CpuFeature::Avx2,
+            CpuFeature::Bmi1,
+            CpuFeature::Bmi2,
+            CpuFeature::Rdseed,
+            CpuFeature::Adx,
+            CpuFeature::Sha,
+            CpuFeature::Avx512f,
+        ]
+    }
+
+    /// Bit position within `machdep.cpu.feature_bits` (CPUID leaf 1 EDX in
+    /// bits 0-31, ECX in bits 32-63). `None` for features that are only
+    /// reported via the CPUID leaf 7 name lists.
+    fn bit_position(self) -> Option<u32> {
+        match self {
+            CpuFeature::Fpu => Some(0),
+            CpuFeature::Mmx => Some(23),
+            CpuFeature::Sse => Some(25),
+            CpuFeature::Sse2 => Some(26),
+            CpuFeature::Sse3 => Some(32),
+            CpuFeature::Pclmulqdq => Some(33),
+            CpuFeature::Ssse3 => Some(41),
+            CpuFeature::Fma => Some(44),
+            CpuFeature::Sse41 => Some(51),
+            CpuFeature::Sse42 => Some(52),
+            CpuFeature::Popcnt => Some(55),
+            CpuFeature::Aes => Some(57),
+            CpuFeature::Avx => Some(60),
+            CpuFeature::Rdrand => Some(62),
+            CpuFeature::Avx2
+            | CpuFeature::Bmi1
+            | CpuFeature::Bmi2
+            | CpuFeature::Rdseed
+            | CpuFeature::Adx
+            | CpuFeature::Sha
+            | CpuFeature::Avx512f => None,
+        }
+    }
+
+    /// Matches a single space-separated token from Darwin's
+    /// `machdep.cpu.features` or `machdep.cpu.leaf7_features` (e.g.
+    /// `"AVX2"`, `"SSE4.1"`, `"BMI2"`).
+    fn from_feature_name(name: &str) -> Option<Self> {
+        match name {
+            "FPU" => Some(CpuFeature::Fpu),
+            "MMX" => Some(CpuFeature::Mmx),
+            "SSE" => Some(CpuFeature::Sse),
+            "SSE2" => Some(CpuFeature::Sse2),
+            "SSE3" => Some(CpuFeature::Sse3),
+            "PCLMULQDQ" => Some(CpuFeature::Pclmulqdq),
+            "SSSE3" => Some(CpuFeature::Ssse3),
+            "FMA" => Some(CpuFeature::Fma),
+            "SSE4.1" => Some(CpuFeature::Sse41),
+            "SSE4.2" => Some(CpuFeature::Sse42),
+            "POPCNT" => Some(CpuFeature::Popcnt),
+            "AES" => Some(CpuFeature::Aes),
+            "AVX1.0" | "AVX" => Some(CpuFeature::Avx),
+            "RDRAND" => Some(CpuFeature::Rdrand),
+            "AVX2" => Some(CpuFeature::Avx2),
+            "BMI1" => Some(CpuFeature::Bmi1),
+            "BMI2" => Some(CpuFeature::Bmi2),
+            "RDSEED" => Some(CpuFeature::Rdseed),
+            "ADX" => Some(CpuFeature::Adx),
+            "SHA" => Some(CpuFeature::Sha),
+            "AVX512F" => Some(CpuFeature::Avx512f),
+            _ => None,
+        }
+    }
+
+    /// Matches a single lowercase, underscore-separated token from Linux's
+    /// `/proc/cpuinfo` `flags` field (e.g. `"avx2"`, `"sse4_1"`, `"bmi2"`).
+    fn from_linux_flag(name: &str) -> Option<Self> {
+        match name {
+            "fpu" => Some(CpuFeature::Fpu),
+            "mmx" => Some(CpuFeature::Mmx),
+            "sse" => Some(CpuFeature::Sse),
+            "sse2" => Some(CpuFeature::Sse2),
+            "pni" | "sse3" => Some(CpuFeature::Sse3),
+            "pclmulqdq" => Some(CpuFeature::Pclmulqdq),
+            "ssse3" => Some(CpuFeature::Ssse3),
+            "fma" => Some(CpuFeature::Fma),
+            "sse4_1" => Some(CpuFeature::Sse41),
+            "sse4_2" => Some(CpuFeature::Sse42),
+            "popcnt" => Some(CpuFeature::Popcnt),
+            "aes" => Some(CpuFeature::Aes),
+            "avx" => Some(CpuFeature::Avx),
+            "rdrand" => Some(CpuFeature::Rdrand),
+            "avx2" => Some(CpuFeature::Avx2),
+            "bmi1" => Some(CpuFeature::Bmi1),
+            "bmi2" => Some(CpuFeature::Bmi2),
+            "rdseed" => Some(CpuFeature::Rdseed),
+            "adx" => Some(CpuFeature::Adx),
+            "sha_ni" | "sha" => Some(CpuFeature::Sha),
+            "avx512f" => Some(CpuFeature::Avx512f),
+            _ => None,
+        }
+    }
+}
+
+/// Decoded set of named ISA extensions for the CPU.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
+pub struct CpuFeatures {
+    /// Raw value of `machdep.cpu.feature_bits`, kept for round-tripping.
+    /// Empty on backends (e.g. Linux) that have no equivalent bitmask.
+    pub raw: String,
+    /// Decoded named features.
+    pub flags: Vec<CpuFeature>,
+}
+
+impl CpuFeatures {
+    /// Parses `machdep.cpu.feature_bits` (decimal or `0x`-prefixed hex) and
+    /// folds in the space-separated `machdep.cpu.features` and
+    /// `machdep.cpu.leaf7_features` sysctls (Intel Macs only) as a fallback
+    /// source of names for flags not present in `feature_bits` itself.
+    pub fn from_sysctl(raw: &str, extra_names: &[&str]) -> Self {
+        let bits = parse_feature_bitmask(raw);
+
+        let mut flags: Vec<CpuFeature> = CpuFeature::all()
+            .iter()
+            .copied()
+            .filter(|feature| {
+                feature
+                    .bit_position()
+                    .is_some_and(|pos| bits & (1u64 << pos) != 0)
+            })
+            .collect();
+
+        for names in extra_names {
+            for name in names.split_whitespace() {
+                if let Some(feature) = CpuFeature::from_feature_name(name) {
+                    if !flags.contains(&feature) {
+                        flags.push(feature);
+                    }
+                }
+            }
+        }
+
+        CpuFeatures {
+            raw: raw.to_string(),
+            flags,
+        }
+    }
+
+    /// Builds a feature set from a Linux `/proc/cpuinfo` `flags` field.
+    pub fn from_linux_flags(flags_field: &str) -> Self {
+        let mut flags = Vec::new();
+        for name in flags_field.split_whitespace() {
+            if let Some(feature) = CpuFeature::from_linux_flag(name) {
+                if !flags.contains(&feature) {
+                    flags.push(feature);
+                }
+            }
+        }
+
+        CpuFeatures {
+            raw: String::new(),
+            flags,
         }
     }
+
+    /// Returns whether a specific feature was decoded.
+    pub fn has_feature(&self, feature: CpuFeature) -> bool {
+        self.flags.contains(&feature)
+    }
+}
+
+/// Parses a `feature_bits`-style sysctl string as decimal or `0x`-prefixed
+/// hex, defaulting to 0 when it cannot be parsed.
+fn parse_feature_bitmask(raw: &str) -> u64 {
+    let raw = raw.trim();
+    raw.strip_prefix("0x")
+        .or_else(|| raw.strip_prefix("0X"))
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .or_else(|| raw.parse::<u64>().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feature_bitmask_decimal() {
+        assert_eq!(parse_feature_bitmask("12345"), 12345);
+    }
+
+    #[test]
+    fn parse_feature_bitmask_hex() {
+        assert_eq!(parse_feature_bitmask("0xBFEBFBFF"), 0xBFEBFBFF);
+        assert_eq!(parse_feature_bitmask("0Xbfebfbff"), 0xBFEBFBFF);
+    }
+
+    #[test]
+    fn parse_feature_bitmask_invalid_defaults_to_zero() {
+        assert_eq!(parse_feature_bitmask("not a number"), 0);
+        assert_eq!(parse_feature_bitmask(""), 0);
+    }
+
+    #[test]
+    fn contiguous_domains_splits_uneven_core_counts() {
+        // 5 logical cores, 2 per domain: ceil(5 / 2) == 3 domains, with the
+        // last one holding only the remainder.
+        let domains = contiguous_domains(5, 2, "L2");
+        assert_eq!(domains.len(), 3);
+        assert_eq!(domains[0].cpus, vec![0, 1]);
+        assert_eq!(domains[1].cpus, vec![2, 3]);
+        assert_eq!(domains[2].cpus, vec![4]);
+        assert_eq!(domains[0].id, "L2-ID0");
+    }
+
+    #[test]
+    fn build_cache_topology_omits_absent_levels() {
+        let topology = build_cache_topology(5, 2, 0);
+        assert_eq!(topology.len(), 1);
+        assert_eq!(topology[0].level, "L2");
+        assert_eq!(topology[0].domains.len(), 3);
+    }
+
+    #[test]
+    fn build_cache_topology_covers_l2_and_l3() {
+        // 6 cores, 2 per L2 domain (3 domains), 4 per L3 domain (ceil(6/4)
+        // == 2 domains, uneven split).
+        let topology = build_cache_topology(6, 2, 4);
+        assert_eq!(topology.len(), 2);
+        assert_eq!(topology[0].level, "L2");
+        assert_eq!(topology[0].domains.len(), 3);
+        assert_eq!(topology[1].level, "L3");
+        assert_eq!(topology[1].domains.len(), 2);
+        assert_eq!(topology[1].domains[1].cpus, vec![4, 5]);
+    }
 }
 
 /// Information about the number of physical and logical cores.
@@ -87,33 +356,27 @@ pub struct CPUCoreCounts {
     pub max_logical: i32,
 }
 
-impl CPUCoreCounts {
-    /// Creates a new CPUCoreCounts instance by fetching data from sysctl.
-    pub fn from_sysctl() -> Self {
-        CPUCoreCounts {
-            physical: get_sysctl_value("hw.physicalcpu"),
-            logical: get_sysctl_value("hw.logicalcpu"),
-            max_physical: get_sysctl_value("hw.physicalcpu_max"),
-            max_logical: get_sysctl_value("hw.logicalcpu_max"),
-        }
-    }
-}
-
 /// CPU frequency information.
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
 pub struct CPUFrequency {
     /// sysctl: hw.cpufrequency (Note: May not be accurate or available on modern Macs)
     pub hz: Long,
+    /// Cycle-counter-based estimate used when `hz` is unavailable (reported
+    /// as 0), behind the `measure-frequency` feature. See
+    /// [`mod@measure`] for how it's derived and its accuracy caveats.
+    pub measured_hz: Option<Long>,
 }
 
-impl CPUFrequency {
-    /// Creates a new CPUFrequency instance by fetching data from sysctl.
-    pub fn from_sysctl() -> Self {
-        CPUFrequency {
-            hz: get_sysctl_value("hw.cpufrequency"),
-        }
-    }
+/// Fills in `measured_hz` from the cycle-counter fallback when `hz` could
+/// not be determined from the OS.
+pub(crate) fn frequency_with_fallback(hz: Long) -> CPUFrequency {
+    let measured_hz = if hz == 0 {
+        measure::measured_hz()
+    } else {
+        None
+    };
+    CPUFrequency { hz, measured_hz }
 }
 
 /// Represents a performance level of the CPU (e.g., Performance Cores, Efficiency Cores)
@@ -122,37 +385,13 @@ impl CPUFrequency {
 pub struct PerformanceLevel {
     /// sysctl: Internal identifier, typically derived from perflevel0, perflevel1, etc.
     pub id: i32,
+    /// sysctl: perflevelX.logicalcpu
+    pub logical_cores: i32,
     pub cache: CacheInfo,
     pub cache_sharing: CacheSharing,
-}
-
-impl PerformanceLevel {
-    /// Attempts to create a PerformanceLevel from a given level ID.
-    /// Returns None if the specific perflevelX sysctl keys are not found.
-    pub fn from_sysctl_id(id: i32) -> Self {
-        // No Option
-        PerformanceLevel {
-            id,
-            cache: CacheInfo::from_sysctl_id(id), // No `?` needed
-            cache_sharing: CacheSharing::from_sysctl_id(id), // No `?` needed
-        }
-    }
-
-    /// Fetches all available PerformanceLevel instances by iterating through perflevelX.
-    pub fn all_from_sysctl() -> Vec<Self> {
-        (0..)
-            .map(|id| {
-                let test_key = format!("hw.perflevel{id}.l1icachesize");
-                if Ctl::new(&test_key).is_ok() {
-                    Some(PerformanceLevel::from_sysctl_id(id))
-                } else {
-                    None
-                }
-            })
-            .take_while(|level| level.is_some())
-            .flatten()
-            .collect()
-    }
+    /// Per-cache-level domains of logical CPUs that share an L2/L3, modeled
+    /// on perf's `--per-cache` aggregation.
+    pub cache_topology: Vec<CacheTopology>,
 }
 
 /// Detailed cache information for a specific performance level.
@@ -169,20 +408,6 @@ pub struct CacheInfo {
     pub l3_bytes: Long,
 }
 
-impl CacheInfo {
-    /// Creates a new CacheInfo instance for a specific performance level ID.
-    pub fn from_sysctl_id(id: i32) -> Self {
-        // No Option
-        let prefix = format!("hw.perflevel{id}");
-        CacheInfo {
-            l1_instruction_bytes: get_sysctl_value(&format!("{prefix}.l1icachesize")),
-            l1_data_bytes: get_sysctl_value(&format!("{prefix}.l1dcachesize")),
-            l2_bytes: get_sysctl_value(&format!("{prefix}.l2cachesize")),
-            l3_bytes: get_sysctl_value(&format!("{prefix}.l3cachesize")),
-        }
-    }
-}
-
 /// Information about how cores share caches.
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
@@ -193,15 +418,117 @@ pub struct CacheSharing {
     pub cores_per_l3: i32,
 }
 
-impl CacheSharing {
-    /// Creates a new CacheSharing instance for a specific performance level ID.
-    pub fn from_sysctl_id(id: i32) -> Self {
-        // No Option
-        let prefix = format!("hw.perflevel{id}");
+/// A single cache-sharing domain: the logical CPUs that share one L2/L3
+/// instance, identified by a stable `"{level}-ID{n}"` id (e.g. `"L2-ID0"`).
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
+pub struct CacheDomain {
+    pub id: String,
+    pub cpus: Vec<i32>,
+}
 
-        CacheSharing {
-            cores_per_l2: get_sysctl_value(&format!("{prefix}.cpusperl2")),
-            cores_per_l3: get_sysctl_value(&format!("{prefix}.cpusperl3")), // L3 might be 0 if not present
-        }
+/// The cache-sharing domains for one cache level (`"L2"` or `"L3"`) within
+/// a performance level.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
+pub struct CacheTopology {
+    pub level: String,
+    pub domains: Vec<CacheDomain>,
+}
+
+/// Builds cache-topology domains from core *counts* by assuming a
+/// contiguous assignment: CPU index `i` belongs to domain `i /
+/// cores_per_domain`, and the number of domains is `ceil(logical /
+/// cores_per_domain)`. A `cores_per_domain` of 0 means the level doesn't
+/// exist and is omitted. Backends that can read the exact grouping (e.g.
+/// Linux's `shared_cpu_list`) should build `CacheTopology` directly instead
+/// of using this, since contiguous assignment doesn't hold for chiplet
+/// parts where LLC domains don't map to contiguous ranges.
+pub(crate) fn build_cache_topology(
+    logical: i32,
+    cores_per_l2: i32,
+    cores_per_l3: i32,
+) -> Vec<CacheTopology> {
+    let mut topology = Vec::new();
+
+    if cores_per_l2 > 0 {
+        topology.push(CacheTopology {
+            level: "L2".to_string(),
+            domains: contiguous_domains(logical, cores_per_l2, "L2"),
+        });
+    }
+    if cores_per_l3 > 0 {
+        topology.push(CacheTopology {
+            level: "L3".to_string(),
+            domains: contiguous_domains(logical, cores_per_l3, "L3"),
+        });
+    }
+
+    topology
+}
+
+/// Splits `0..logical` into contiguous domains of `cores_per_domain` CPUs.
+fn contiguous_domains(logical: i32, cores_per_domain: i32, level: &str) -> Vec<CacheDomain> {
+    let domain_count = (logical + cores_per_domain - 1) / cores_per_domain;
+    (0..domain_count)
+        .map(|domain_id| {
+            let start = domain_id * cores_per_domain;
+            let end = ((domain_id + 1) * cores_per_domain).min(logical);
+            CacheDomain {
+                id: format!("{level}-ID{domain_id}"),
+                cpus: (start..end).collect(),
+            }
+        })
+        .collect()
+}
+
+/// The 1/5/15-minute load averages.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
+pub struct LoadAverage {
+    pub one_minute: f64,
+    pub five_minute: f64,
+    pub fifteen_minute: f64,
+}
+
+/// A live CPU-utilization sample: per-core and aggregate usage percentages
+/// plus the system load average. Returned by [`CpuSampler::refresh`].
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "gql", derive(async_graphql::SimpleObject))]
+pub struct CpuUsage {
+    /// Per-core utilization percentages (0.0-100.0), in core-index order.
+    pub per_core: Vec<f32>,
+    /// Aggregate utilization percentage (0.0-100.0) across all cores.
+    pub total: f32,
+    pub load_avg: LoadAverage,
+}
+
+/// Samples live CPU utilization and load average over time.
+///
+/// Unlike [`CPUInfo`], which is a one-shot static snapshot, `CpuSampler`
+/// holds the previous sample's platform-specific counters so each
+/// [`refresh`](CpuSampler::refresh) call can diff against it. The first
+/// call after [`CpuSampler::new`] has nothing to diff against, so its
+/// usage percentages are all zero; `load_avg` is read as on any other call.
+pub struct CpuSampler {
+    previous: Option<<CurrentCpuInfoSource as CpuUsageSource>::Snapshot>,
+}
+
+impl Default for CpuSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuSampler {
+    /// Creates a sampler with no prior sample.
+    pub fn new() -> Self {
+        CpuSampler { previous: None }
+    }
+
+    /// Takes a new sample and returns the usage since the last call (zero
+    /// usage percentages on the first call).
+    pub fn refresh(&mut self) -> CpuUsage {
+        CurrentCpuInfoSource::refresh(&mut self.previous)
     }
 }
@@ -0,0 +1,57 @@
+//! Best-effort empirical frequency estimation via a cycle counter, used as
+//! a fallback when the OS reports `0` for the CPU's nominal frequency (as
+//! `hw.cpufrequency` does on Apple Silicon). Gated behind the
+//! `measure-frequency` feature since it costs a fixed sampling delay at
+//! startup.
+
+#[cfg(feature = "measure-frequency")]
+const SAMPLE_DURATION: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Estimates the nominal clock by sampling the x86_64 TSC (`rdtsc`) across
+/// a short known wall-clock interval: `hz = (cycles_end - cycles_start) /
+/// seconds_elapsed`.
+///
+/// Returns `None` when not built with the `measure-frequency` feature, or
+/// when no cycle counter is available for the target architecture.
+///
+/// Caveat: on x86_64 this reads the TSC, which on modern ("invariant
+/// TSC") CPUs ticks at a fixed rate close to the base clock but does not
+/// track per-core turbo or throttling. aarch64 has no equivalent: its
+/// `cntvct_el0` counter ticks at the fixed rate reported by `cntfrq_el0`,
+/// decoupled from the core's actual clock, so diffing it would just
+/// reproduce `cntfrq_el0` (typically ~24 MHz) rather than estimate a
+/// frequency; `read_cycle_counter` returns `None` there instead.
+#[cfg(feature = "measure-frequency")]
+pub(crate) fn measured_hz() -> Option<i64> {
+    let start = std::time::Instant::now();
+    let start_cycles = read_cycle_counter()?;
+    std::thread::sleep(SAMPLE_DURATION);
+    let end_cycles = read_cycle_counter()?;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    if elapsed <= 0.0 {
+        return None;
+    }
+
+    let cycles = end_cycles.saturating_sub(start_cycles);
+    Some((cycles as f64 / elapsed) as i64)
+}
+
+#[cfg(not(feature = "measure-frequency"))]
+pub(crate) fn measured_hz() -> Option<i64> {
+    None
+}
+
+#[cfg(all(feature = "measure-frequency", target_arch = "x86_64"))]
+fn read_cycle_counter() -> Option<u64> {
+    // SAFETY: RDTSC is available on all x86_64 CPUs.
+    Some(unsafe { std::arch::x86_64::_rdtsc() })
+}
+
+#[cfg(all(feature = "measure-frequency", not(target_arch = "x86_64")))]
+fn read_cycle_counter() -> Option<u64> {
+    // No architecture besides x86_64's invariant TSC gives a cycle counter
+    // whose rate tracks the actual CPU clock (see the aarch64 caveat on
+    // `measured_hz`), so there's nothing useful to read here.
+    None
+}
@@ -0,0 +1,248 @@
+//! macOS/BSD backend, backed by the `sysctl` MIB (`machdep.cpu.*`,
+//! `hw.perflevel*`).
+
+use std::str::FromStr;
+
+use sysctl::Ctl;
+use sysctl::Sysctl; // Import Ctl directly
+
+use super::{load_avg, CpuInfoSource, CpuUsageSource};
+use crate::{
+    CPUCoreCounts, CPUFrequency, CPUIdentification, CacheInfo, CacheSharing, CpuFeatures,
+    CpuUsage, PerformanceLevel,
+};
+
+/// Generic helper to get a sysctl value.
+/// Returns a default value if the key is not found, cannot be read, or fails to parse.
+fn get_sysctl_value<T>(key: &str) -> T
+where
+    T: FromStr + Default,
+{
+    Ctl::new(key)
+        .ok()
+        .and_then(|ctl| ctl.value_string().ok())
+        .and_then(|s| s.parse::<T>().ok())
+        .unwrap_or_default()
+}
+
+/// Gathers CPU information from the Darwin/BSD `sysctl` MIB.
+pub struct DarwinCpuInfoSource;
+
+impl CpuInfoSource for DarwinCpuInfoSource {
+    fn identification() -> CPUIdentification {
+        let feature_bits: String = get_sysctl_value("machdep.cpu.feature_bits");
+        let features_str: String = get_sysctl_value("machdep.cpu.features");
+        let leaf7_features: String = get_sysctl_value("machdep.cpu.leaf7_features");
+        let features = CpuFeatures::from_sysctl(&feature_bits, &[&features_str, &leaf7_features]);
+
+        CPUIdentification {
+            brand_string: get_sysctl_value("machdep.cpu.brand_string"),
+            vendor: get_sysctl_value("machdep.cpu.vendor"),
+            feature_bits,
+            features,
+        }
+    }
+
+    fn core_counts() -> CPUCoreCounts {
+        CPUCoreCounts {
+            physical: get_sysctl_value("hw.physicalcpu"),
+            logical: get_sysctl_value("hw.logicalcpu"),
+            max_physical: get_sysctl_value("hw.physicalcpu_max"),
+            max_logical: get_sysctl_value("hw.logicalcpu_max"),
+        }
+    }
+
+    fn frequency() -> CPUFrequency {
+        crate::frequency_with_fallback(get_sysctl_value("hw.cpufrequency"))
+    }
+
+    fn performance_levels() -> Vec<PerformanceLevel> {
+        (0..)
+            .map(|id| {
+                let test_key = format!("hw.perflevel{id}.l1icachesize");
+                if Ctl::new(&test_key).is_ok() {
+                    Some(performance_level_from_sysctl_id(id))
+                } else {
+                    None
+                }
+            })
+            .take_while(|level| level.is_some())
+            .flatten()
+            .collect()
+    }
+}
+
+/// Builds a `PerformanceLevel` from a given `hw.perflevelX` id.
+fn performance_level_from_sysctl_id(id: i32) -> PerformanceLevel {
+    // No Option
+    let logical_cores: i32 = get_sysctl_value(&format!("hw.perflevel{id}.logicalcpu"));
+    let cache_sharing = cache_sharing_from_sysctl_id(id);
+
+    PerformanceLevel {
+        id,
+        logical_cores,
+        cache: cache_info_from_sysctl_id(id),
+        cache_topology: crate::build_cache_topology(
+            logical_cores,
+            cache_sharing.cores_per_l2,
+            cache_sharing.cores_per_l3,
+        ),
+        cache_sharing,
+    }
+}
+
+/// Builds a `CacheInfo` for a specific `hw.perflevelX` id.
+fn cache_info_from_sysctl_id(id: i32) -> CacheInfo {
+    // No Option
+    let prefix = format!("hw.perflevel{id}");
+    CacheInfo {
+        l1_instruction_bytes: get_sysctl_value(&format!("{prefix}.l1icachesize")),
+        l1_data_bytes: get_sysctl_value(&format!("{prefix}.l1dcachesize")),
+        l2_bytes: get_sysctl_value(&format!("{prefix}.l2cachesize")),
+        l3_bytes: get_sysctl_value(&format!("{prefix}.l3cachesize")),
+    }
+}
+
+/// Builds a `CacheSharing` for a specific `hw.perflevelX` id.
+fn cache_sharing_from_sysctl_id(id: i32) -> CacheSharing {
+    // No Option
+    let prefix = format!("hw.perflevel{id}");
+
+    CacheSharing {
+        cores_per_l2: get_sysctl_value(&format!("{prefix}.cpusperl2")),
+        cores_per_l3: get_sysctl_value(&format!("{prefix}.cpusperl3")), // L3 might be 0 if not present
+    }
+}
+
+// Mach host-statistics FFI: `host_processor_info(PROCESSOR_CPU_LOAD_INFO)`
+// returns, per logical CPU, the cumulative tick counts for the 4
+// `CPU_STATE_*` buckets below. Mach is always linked on Darwin, so no
+// `#[link]` attribute is needed.
+type KernReturn = i32;
+type MachPort = u32;
+
+const PROCESSOR_CPU_LOAD_INFO: i32 = 2;
+const CPU_STATE_MAX: usize = 4;
+const CPU_STATE_USER: usize = 0;
+const CPU_STATE_SYSTEM: usize = 1;
+const CPU_STATE_NICE: usize = 3;
+
+extern "C" {
+    fn mach_host_self() -> MachPort;
+    fn mach_task_self() -> MachPort;
+    fn host_processor_info(
+        host: MachPort,
+        flavor: i32,
+        out_processor_count: *mut u32,
+        out_processor_info: *mut *mut i32,
+        out_processor_info_count: *mut u32,
+    ) -> KernReturn;
+    fn vm_deallocate(target_task: MachPort, address: usize, size: usize) -> KernReturn;
+}
+
+/// A `host_processor_info(PROCESSOR_CPU_LOAD_INFO)` sample: the 4
+/// `CPU_STATE_*` tick counters for each logical CPU, in CPU-index order.
+pub struct MachCpuLoadSnapshot {
+    per_core: Vec<[u32; CPU_STATE_MAX]>,
+}
+
+/// Calls `host_processor_info(PROCESSOR_CPU_LOAD_INFO)` and copies its
+/// result into owned memory, freeing the Mach-allocated buffer.
+fn read_processor_cpu_load() -> MachCpuLoadSnapshot {
+    let mut processor_count: u32 = 0;
+    let mut info: *mut i32 = std::ptr::null_mut();
+    let mut info_count: u32 = 0;
+
+    // SAFETY: all out-params are valid pointers to local variables; Mach
+    // fills them in and allocates `info` on success.
+    let result = unsafe {
+        host_processor_info(
+            mach_host_self(),
+            PROCESSOR_CPU_LOAD_INFO,
+            &mut processor_count,
+            &mut info,
+            &mut info_count,
+        )
+    };
+
+    if result != 0 || info.is_null() {
+        return MachCpuLoadSnapshot { per_core: Vec::new() };
+    }
+
+    // SAFETY: `info` points to `info_count` valid `i32`s, as just reported
+    // by `host_processor_info`; `processor_count * CPU_STATE_MAX` fits
+    // within that per the flavor's documented layout.
+    let per_core = unsafe {
+        std::slice::from_raw_parts(info, info_count as usize)
+            .chunks_exact(CPU_STATE_MAX)
+            .take(processor_count as usize)
+            .map(|chunk| [chunk[0] as u32, chunk[1] as u32, chunk[2] as u32, chunk[3] as u32])
+            .collect()
+    };
+
+    // SAFETY: `info` was allocated by Mach for this call and is deallocated
+    // exactly once here, sized by the byte length Mach reported.
+    unsafe {
+        vm_deallocate(
+            mach_task_self(),
+            info as usize,
+            info_count as usize * std::mem::size_of::<i32>(),
+        );
+    }
+
+    MachCpuLoadSnapshot { per_core }
+}
+
+impl CpuUsageSource for DarwinCpuInfoSource {
+    type Snapshot = MachCpuLoadSnapshot;
+
+    fn refresh(previous: &mut Option<Self::Snapshot>) -> CpuUsage {
+        let current = read_processor_cpu_load();
+
+        let usage = match previous {
+            Some(previous) => {
+                let per_core: Vec<f32> = current
+                    .per_core
+                    .iter()
+                    .zip(&previous.per_core)
+                    .map(|(now, before)| cpu_load_usage_percent(before, now))
+                    .collect();
+                let total = if per_core.is_empty() {
+                    0.0
+                } else {
+                    per_core.iter().sum::<f32>() / per_core.len() as f32
+                };
+                CpuUsage {
+                    per_core,
+                    total,
+                    load_avg: load_avg::load_avg(),
+                }
+            }
+            None => CpuUsage {
+                per_core: vec![0.0; current.per_core.len()],
+                total: 0.0,
+                load_avg: load_avg::load_avg(),
+            },
+        };
+
+        *previous = Some(current);
+        usage
+    }
+}
+
+/// Converts two `CPU_STATE_*` tick samples for one core into a busy
+/// percentage (0.0-100.0), counting user+system+nice against the total of
+/// all 4 states.
+fn cpu_load_usage_percent(before: &[u32; CPU_STATE_MAX], after: &[u32; CPU_STATE_MAX]) -> f32 {
+    let busy_before = before[CPU_STATE_USER] + before[CPU_STATE_SYSTEM] + before[CPU_STATE_NICE];
+    let busy_after = after[CPU_STATE_USER] + after[CPU_STATE_SYSTEM] + after[CPU_STATE_NICE];
+    let total_before: u32 = before.iter().sum();
+    let total_after: u32 = after.iter().sum();
+
+    let total_delta = total_after.saturating_sub(total_before);
+    if total_delta == 0 {
+        return 0.0;
+    }
+    let busy_delta = busy_after.saturating_sub(busy_before);
+    (busy_delta as f64 / total_delta as f64 * 100.0) as f32
+}
@@ -0,0 +1,379 @@
+//! Linux backend, backed by `/proc/cpuinfo`, `/proc/stat`, and the
+//! `/sys/devices/system/cpu/cpu*/cache/index*/*` sysfs tree.
+
+use std::collections::HashMap;
+use std::fs;
+
+use super::{load_avg, CpuInfoSource, CpuUsageSource};
+use crate::{
+    CPUCoreCounts, CPUFrequency, CPUIdentification, CacheDomain, CacheInfo, CacheSharing,
+    CacheTopology, CpuFeatures, CpuUsage, PerformanceLevel,
+};
+
+/// Gathers CPU information from `/proc` and `/sys` on Linux.
+pub struct LinuxCpuInfoSource;
+
+impl CpuInfoSource for LinuxCpuInfoSource {
+    fn identification() -> CPUIdentification {
+        let first = cpuinfo_blocks().into_iter().next().unwrap_or_default();
+
+        let brand_string = first.get("model name").cloned().unwrap_or_default();
+        let vendor = first.get("vendor_id").cloned().unwrap_or_default();
+        let flags = first.get("flags").cloned().unwrap_or_default();
+
+        CPUIdentification {
+            brand_string,
+            vendor,
+            feature_bits: String::new(),
+            features: CpuFeatures::from_linux_flags(&flags),
+        }
+    }
+
+    fn core_counts() -> CPUCoreCounts {
+        let blocks = cpuinfo_blocks();
+        let logical = blocks.len() as i32;
+
+        let mut physical_ids: Vec<(String, String)> = blocks
+            .iter()
+            .filter_map(|block| {
+                let physical_id = block.get("physical id")?.clone();
+                let core_id = block.get("core id")?.clone();
+                Some((physical_id, core_id))
+            })
+            .collect();
+        physical_ids.sort();
+        physical_ids.dedup();
+        let physical = if physical_ids.is_empty() {
+            logical
+        } else {
+            physical_ids.len() as i32
+        };
+
+        let max_logical = possible_cpu_count().unwrap_or(logical);
+
+        CPUCoreCounts {
+            physical,
+            logical,
+            max_physical: physical,
+            max_logical,
+        }
+    }
+
+    fn frequency() -> CPUFrequency {
+        crate::frequency_with_fallback(nominal_frequency_hz())
+    }
+
+    fn performance_levels() -> Vec<PerformanceLevel> {
+        // Linux has no native notion of Apple's heterogeneous performance
+        // levels, so the whole machine is reported as a single level backed
+        // by CPU 0's cache sizes and the kernel's exact sharing domains.
+        let mut cache_topology = Vec::new();
+        let l2_domains = cache_topology_for_level("2", "L2");
+        if !l2_domains.is_empty() {
+            cache_topology.push(CacheTopology {
+                level: "L2".to_string(),
+                domains: l2_domains,
+            });
+        }
+        let l3_domains = cache_topology_for_level("3", "L3");
+        if !l3_domains.is_empty() {
+            cache_topology.push(CacheTopology {
+                level: "L3".to_string(),
+                domains: l3_domains,
+            });
+        }
+
+        vec![PerformanceLevel {
+            id: 0,
+            logical_cores: Self::core_counts().logical,
+            cache: cache_info_for_cpu(0),
+            cache_sharing: cache_sharing_for_cpu(0),
+            cache_topology,
+        }]
+    }
+}
+
+/// One `/proc/stat` CPU line's jiffy counters, reduced to what's needed to
+/// compute a busy percentage: `busy` is `user+nice+system`, `total` is the
+/// sum of the fields from `user` through `steal` (including `idle`,
+/// `iowait`, and so on, but not `guest`/`guest_nice`).
+#[derive(Clone, Copy, Default)]
+struct ProcStatTicks {
+    busy: u64,
+    total: u64,
+}
+
+/// A `/proc/stat` sample: the aggregate `cpu` line plus one `cpuN` line per
+/// logical CPU, in CPU-index order.
+pub struct ProcStatSnapshot {
+    total: ProcStatTicks,
+    per_core: Vec<ProcStatTicks>,
+}
+
+impl CpuUsageSource for LinuxCpuInfoSource {
+    type Snapshot = ProcStatSnapshot;
+
+    fn refresh(previous: &mut Option<Self::Snapshot>) -> CpuUsage {
+        let current = read_proc_stat();
+
+        let usage = match previous {
+            Some(previous) => CpuUsage {
+                per_core: current
+                    .per_core
+                    .iter()
+                    .zip(&previous.per_core)
+                    .map(|(now, before)| usage_percent(*before, *now))
+                    .collect(),
+                total: usage_percent(previous.total, current.total),
+                load_avg: load_avg::load_avg(),
+            },
+            None => CpuUsage {
+                per_core: vec![0.0; current.per_core.len()],
+                total: 0.0,
+                load_avg: load_avg::load_avg(),
+            },
+        };
+
+        *previous = Some(current);
+        usage
+    }
+}
+
+/// Reads and parses `/proc/stat`'s `cpu`/`cpuN` lines.
+fn read_proc_stat() -> ProcStatSnapshot {
+    let contents = fs::read_to_string("/proc/stat").unwrap_or_default();
+
+    let mut total = ProcStatTicks::default();
+    let mut per_core = Vec::new();
+    for line in contents.lines() {
+        let Some(rest) = line.strip_prefix("cpu") else {
+            continue;
+        };
+        let is_aggregate = rest.starts_with(' ');
+        // `cpuN` lines carry the core index directly before the tick
+        // fields (e.g. `"cpu0 16693 0 ..."` strips to `"0 16693 0 ..."`),
+        // so that index has to come off too before parsing the fields.
+        let fields = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        let Some(ticks) = parse_proc_stat_ticks(fields) else {
+            continue;
+        };
+        if is_aggregate {
+            total = ticks;
+        } else {
+            per_core.push(ticks);
+        }
+    }
+
+    ProcStatSnapshot { total, per_core }
+}
+
+/// Parses the space-separated jiffy fields following `cpu`/`cpuN` on a
+/// `/proc/stat` line into `busy` (`user+nice+system`) and `total` (the
+/// first 8 fields, `user` through `steal`, summed).
+fn parse_proc_stat_ticks(rest: &str) -> Option<ProcStatTicks> {
+    let fields: Vec<u64> = rest.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+    if fields.len() < 3 {
+        return None;
+    }
+    let busy = fields[0] + fields.get(1).copied().unwrap_or(0) + fields[2];
+    // Only sum through `steal` (index 7): `guest`/`guest_nice` (8/9) are
+    // already folded into `user`/`nice` by the kernel, so including them
+    // too would double-count guest time on VM/container hosts.
+    let total = fields.iter().take(8).sum();
+    Some(ProcStatTicks { busy, total })
+}
+
+/// Converts two `/proc/stat` samples into a busy percentage (0.0-100.0).
+fn usage_percent(before: ProcStatTicks, after: ProcStatTicks) -> f32 {
+    let total_delta = after.total.saturating_sub(before.total);
+    if total_delta == 0 {
+        return 0.0;
+    }
+    let busy_delta = after.busy.saturating_sub(before.busy);
+    (busy_delta as f64 / total_delta as f64 * 100.0) as f32
+}
+
+/// Parses `/proc/cpuinfo` into one key/value map per logical CPU block.
+fn cpuinfo_blocks() -> Vec<HashMap<String, String>> {
+    let contents = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+
+    let mut blocks = Vec::new();
+    let mut current = HashMap::new();
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+/// Reads CPU 0's nominal (rated) clock, analogous to Darwin's
+/// `hw.cpufrequency`. Prefers `cpuinfo_max_freq`, a stable hardware limit,
+/// over `/proc/cpuinfo`'s `cpu MHz`, which tracks the *current* scaling
+/// frequency and constantly changes under powersave/turbo.
+fn nominal_frequency_hz() -> i64 {
+    if let Some(khz) = fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq")
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+    {
+        return khz * 1_000;
+    }
+
+    let first = cpuinfo_blocks().into_iter().next().unwrap_or_default();
+    let mhz: f64 = first
+        .get("cpu MHz")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.0);
+    (mhz * 1_000_000.0) as i64
+}
+
+/// Parses `/sys/devices/system/cpu/possible` (e.g. `"0-7"`) into a count.
+fn possible_cpu_count() -> Option<i32> {
+    let contents = fs::read_to_string("/sys/devices/system/cpu/possible").ok()?;
+    let range = contents.trim();
+    match range.split_once('-') {
+        Some((low, high)) => {
+            let low: i32 = low.parse().ok()?;
+            let high: i32 = high.parse().ok()?;
+            Some(high - low + 1)
+        }
+        None => range.parse().ok().map(|n: i32| n + 1),
+    }
+}
+
+/// Reads a single file under `/sys/devices/system/cpu/cpu{cpu}/cache/index{index}/`.
+fn cache_sysfs_value(cpu: u32, index: u32, file: &str) -> Option<String> {
+    let path = format!("/sys/devices/system/cpu/cpu{cpu}/cache/index{index}/{file}");
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Parses a sysfs cache size string (e.g. `"32K"`, `"1M"`) into bytes.
+fn parse_cache_size(raw: &str) -> i64 {
+    let raw = raw.trim();
+    let (digits, multiplier) = match raw.strip_suffix('K').or_else(|| raw.strip_suffix('k')) {
+        Some(digits) => (digits, 1024),
+        None => match raw.strip_suffix('M') {
+            Some(digits) => (digits, 1024 * 1024),
+            None => (raw, 1),
+        },
+    };
+    digits.parse::<i64>().unwrap_or(0) * multiplier
+}
+
+/// Finds the sysfs `cache/indexN` directory for a given cache level and type
+/// (`"Instruction"`, `"Data"`, or `"Unified"`) on the given CPU.
+fn cache_index_for(cpu: u32, level: &str, cache_type: Option<&str>) -> Option<u32> {
+    (0..8).find(|&index| {
+        let Some(found_level) = cache_sysfs_value(cpu, index, "level") else {
+            return false;
+        };
+        if found_level != level {
+            return false;
+        }
+        match cache_type {
+            Some(expected) => cache_sysfs_value(cpu, index, "type").as_deref() == Some(expected),
+            None => true,
+        }
+    })
+}
+
+/// Builds a `CacheInfo` from the given CPU's sysfs cache entries.
+fn cache_info_for_cpu(cpu: u32) -> CacheInfo {
+    let size_for = |level: &str, cache_type: Option<&str>| {
+        cache_index_for(cpu, level, cache_type)
+            .and_then(|index| cache_sysfs_value(cpu, index, "size"))
+            .map(|raw| parse_cache_size(&raw))
+            .unwrap_or(0)
+    };
+
+    CacheInfo {
+        l1_instruction_bytes: size_for("1", Some("Instruction")),
+        l1_data_bytes: size_for("1", Some("Data")),
+        l2_bytes: size_for("2", None),
+        l3_bytes: size_for("3", None),
+    }
+}
+
+/// Builds a `CacheSharing` from the number of CPUs listed in each cache
+/// level's `shared_cpu_list`.
+fn cache_sharing_for_cpu(cpu: u32) -> CacheSharing {
+    let shared_count = |level: &str| {
+        cache_index_for(cpu, level, None)
+            .and_then(|index| cache_sysfs_value(cpu, index, "shared_cpu_list"))
+            .map(|list| parse_cpu_list_count(&list))
+            .unwrap_or(0)
+    };
+
+    CacheSharing {
+        cores_per_l2: shared_count("2"),
+        cores_per_l3: shared_count("3"),
+    }
+}
+
+/// Counts the CPUs described by a sysfs CPU list like `"0-3"` or `"0,2,4"`.
+fn parse_cpu_list_count(list: &str) -> i32 {
+    parse_cpu_list(list).len() as i32
+}
+
+/// Expands a sysfs CPU list like `"0-3"` or `"0,2,4"` into CPU indices.
+fn parse_cpu_list(list: &str) -> Vec<i32> {
+    list.split(',')
+        .filter(|s| !s.is_empty())
+        .flat_map(|part| match part.split_once('-') {
+            Some((low, high)) => {
+                let low: i32 = low.parse().unwrap_or(0);
+                let high: i32 = high.parse().unwrap_or(low);
+                (low..=high).collect::<Vec<_>>()
+            }
+            None => vec![part.parse().unwrap_or(0)],
+        })
+        .collect()
+}
+
+/// Builds the cache-sharing domains for a cache level by reading every
+/// logical CPU's `shared_cpu_list` and grouping CPUs that report the same
+/// list, so chiplet parts whose LLC domains aren't contiguous are grouped
+/// correctly rather than assumed.
+fn cache_topology_for_level(level: &str, label: &str) -> Vec<CacheDomain> {
+    let cpu_count = possible_cpu_count().unwrap_or_else(|| cpuinfo_blocks().len() as i32);
+
+    let mut seen_lists: Vec<String> = Vec::new();
+    let mut domains: Vec<Vec<i32>> = Vec::new();
+    for cpu in 0..cpu_count.max(0) as u32 {
+        let Some(index) = cache_index_for(cpu, level, None) else {
+            continue;
+        };
+        let Some(list) = cache_sysfs_value(cpu, index, "shared_cpu_list") else {
+            continue;
+        };
+        if let Some(position) = seen_lists.iter().position(|seen| seen == &list) {
+            if !domains[position].contains(&(cpu as i32)) {
+                domains[position].push(cpu as i32);
+            }
+        } else {
+            seen_lists.push(list.clone());
+            domains.push(parse_cpu_list(&list));
+        }
+    }
+
+    domains
+        .into_iter()
+        .enumerate()
+        .map(|(domain_id, mut cpus)| {
+            cpus.sort_unstable();
+            CacheDomain {
+                id: format!("{label}-ID{domain_id}"),
+                cpus,
+            }
+        })
+        .collect()
+}
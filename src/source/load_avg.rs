@@ -0,0 +1,30 @@
+//! Shared `getloadavg(3)` wrapper for the Unix-like backends (Darwin and
+//! Linux); Windows has no equivalent syscall and approximates load average
+//! separately (see `windows::LOAD_EMA_1` and friends).
+
+use crate::LoadAverage;
+
+extern "C" {
+    fn getloadavg(loadavg: *mut f64, nelem: i32) -> i32;
+}
+
+/// Reads the 1/5/15-minute load averages via `getloadavg(3)`. Returns all
+/// zeros if the call fails.
+pub(crate) fn load_avg() -> LoadAverage {
+    let mut loads = [0.0f64; 3];
+    // SAFETY: `loads` has room for the 3 samples `nelem` requests.
+    let filled = unsafe { getloadavg(loads.as_mut_ptr(), 3) };
+    if filled == 3 {
+        LoadAverage {
+            one_minute: loads[0],
+            five_minute: loads[1],
+            fifteen_minute: loads[2],
+        }
+    } else {
+        LoadAverage {
+            one_minute: 0.0,
+            five_minute: 0.0,
+            fifteen_minute: 0.0,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Platform-specific backends for gathering CPU information.
+//!
+//! Each backend implements [`CpuInfoSource`] against whatever OS-native
+//! source is available (sysctl, `/proc`+`/sys`, or the Win32 APIs), so
+//! [`crate::CPUInfo::new`] produces an identical shape regardless of
+//! platform.
+
+use crate::{CPUCoreCounts, CPUFrequency, CPUIdentification, CpuUsage, PerformanceLevel};
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub mod darwin;
+
+#[cfg(target_os = "linux")]
+pub mod linux;
+
+#[cfg(target_os = "windows")]
+pub mod windows;
+
+#[cfg(unix)]
+mod load_avg;
+
+/// A platform-specific source of CPU information.
+///
+/// Implementors fetch the same logical fields (identification, core
+/// counts, frequency, and per-performance-level cache info) from whatever
+/// OS-native source is available.
+pub trait CpuInfoSource {
+    /// Fetches CPU identification and branding.
+    fn identification() -> CPUIdentification;
+    /// Fetches physical/logical core counts.
+    fn core_counts() -> CPUCoreCounts;
+    /// Fetches the nominal CPU frequency.
+    fn frequency() -> CPUFrequency;
+    /// Fetches all available performance levels and their cache info.
+    fn performance_levels() -> Vec<PerformanceLevel>;
+}
+
+/// A platform-specific source of live CPU utilization and load-average
+/// samples, for use by [`crate::CpuSampler`].
+///
+/// Implementors own whatever state (previous tick counters, an
+/// exponential-moving-average accumulator, ...) they need to turn one more
+/// sample into a [`CpuUsage`] diff; `previous` starts as `None` on the
+/// first call.
+pub trait CpuUsageSource {
+    /// Backend-specific state carried between samples.
+    type Snapshot;
+
+    /// Takes a new sample, diffs it against `previous` (if any), stores
+    /// the new sample back into `previous`, and returns the resulting
+    /// usage snapshot.
+    fn refresh(previous: &mut Option<Self::Snapshot>) -> CpuUsage;
+}
@@ -0,0 +1,302 @@
+//! Windows backend, backed by `GetLogicalProcessorInformationEx` (core and
+//! cache topology) and `CallNtPowerInformation` (nominal frequency).
+//!
+//! These are raw Win32 FFI calls (via `windows-sys`), so every entry point
+//! here is `unsafe` at the syscall boundary; everything above that boundary
+//! stays safe Rust.
+
+use std::mem;
+
+use windows_sys::Win32::Foundation::FILETIME;
+use windows_sys::Win32::System::Power::{
+    CallNtPowerInformation, ProcessorInformation, PROCESSOR_POWER_INFORMATION,
+};
+use windows_sys::Win32::System::SystemInformation::{
+    GetLogicalProcessorInformationEx, GetSystemTimes, GROUP_AFFINITY, RelationAll, RelationCache,
+    RelationProcessorCore, CACHE_RELATIONSHIP, LOGICAL_PROCESSOR_RELATIONSHIP,
+    PROCESSOR_RELATIONSHIP, SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX,
+};
+
+use super::{CpuInfoSource, CpuUsageSource};
+use crate::{
+    CPUCoreCounts, CPUFrequency, CPUIdentification, CacheInfo, CacheSharing, CpuFeatures, CpuUsage,
+};
+use crate::PerformanceLevel;
+
+/// Decay factors for the 1/5/15-minute exponential-moving-average load
+/// average, matching the constants the Linux kernel (and the `sysinfo`
+/// crate's Windows backend) use per 5-second sampling tick. Windows has no
+/// native load-average concept, so this approximates it from the
+/// instantaneous busy fraction reported by [`GetSystemTimes`].
+///
+/// These weights assume [`CpuSampler::refresh`](crate::CpuSampler::refresh)
+/// is called roughly every 5 seconds; calling it much faster or slower
+/// skews the blended average toward the instantaneous sample or the prior
+/// history respectively, since the decay has no notion of elapsed time.
+const LOAD_EMA_1: f64 = 0.9200444146293232;
+const LOAD_EMA_5: f64 = 0.9834714538216174;
+const LOAD_EMA_15: f64 = 0.9944598480048967;
+
+/// Gathers CPU information from the Win32 system-information APIs.
+pub struct WindowsCpuInfoSource;
+
+impl CpuInfoSource for WindowsCpuInfoSource {
+    fn identification() -> CPUIdentification {
+        // Win32 has no single sysctl-style brand/vendor/feature-bits MIB;
+        // those would come from the CPUID instruction directly, which is
+        // out of scope for this backend.
+        CPUIdentification {
+            brand_string: String::new(),
+            vendor: String::new(),
+            feature_bits: String::new(),
+            features: CpuFeatures::from_linux_flags(""),
+        }
+    }
+
+    fn core_counts() -> CPUCoreCounts {
+        let (buffer, offsets) = relationship_records(RelationAll);
+
+        let mut physical = 0i32;
+        let mut logical = 0i32;
+        for &offset in &offsets {
+            // SAFETY: `offset` was returned by `relationship_records` for
+            // this same `buffer` and points at a complete record.
+            let record: &SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX =
+                unsafe { &*(buffer.as_ptr().add(offset) as *const _) };
+            if record.Relationship == RelationProcessorCore {
+                physical += 1;
+                // SAFETY: `Relationship` confirms the `Processor` variant is active.
+                let processor: &PROCESSOR_RELATIONSHIP = unsafe { &record.Anonymous.Processor };
+                for group in processor_groups(processor) {
+                    logical += group.Mask.count_ones() as i32;
+                }
+            }
+        }
+
+        CPUCoreCounts {
+            physical,
+            logical,
+            max_physical: physical,
+            max_logical: logical,
+        }
+    }
+
+    fn frequency() -> CPUFrequency {
+        // CallNtPowerInformation(ProcessorInformation, ...) requires a
+        // buffer holding one PROCESSOR_POWER_INFORMATION entry per logical
+        // processor, not just one; undersizing it fails the call outright
+        // on any multi-core machine.
+        let logical = Self::core_counts().logical.max(1) as usize;
+        let mut info: Vec<PROCESSOR_POWER_INFORMATION> =
+            std::iter::repeat_with(|| unsafe { mem::zeroed() })
+                .take(logical)
+                .collect();
+        let size = (mem::size_of::<PROCESSOR_POWER_INFORMATION>() * logical) as u32;
+
+        // SAFETY: `info` holds `logical` PROCESSOR_POWER_INFORMATION
+        // records, matching `size`.
+        let status = unsafe {
+            CallNtPowerInformation(
+                ProcessorInformation,
+                std::ptr::null(),
+                0,
+                info.as_mut_ptr() as *mut _,
+                size,
+            )
+        };
+
+        let mhz = if status == 0 { info[0].MaxMhz } else { 0 };
+
+        crate::frequency_with_fallback(i64::from(mhz) * 1_000_000)
+    }
+
+    fn performance_levels() -> Vec<PerformanceLevel> {
+        let (buffer, offsets) = relationship_records(RelationAll);
+
+        let mut cache = CacheInfo {
+            l1_instruction_bytes: 0,
+            l1_data_bytes: 0,
+            l2_bytes: 0,
+            l3_bytes: 0,
+        };
+
+        for &offset in &offsets {
+            // SAFETY: `offset` was returned by `relationship_records` for
+            // this same `buffer` and points at a complete record.
+            let record: &SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX =
+                unsafe { &*(buffer.as_ptr().add(offset) as *const _) };
+            if record.Relationship != RelationCache {
+                continue;
+            }
+            // SAFETY: `Relationship` confirms the `Cache` variant is active.
+            let info: &CACHE_RELATIONSHIP = unsafe { &record.Anonymous.Cache };
+            let size = i64::from(info.CacheSize);
+            match (info.Level, info.Type) {
+                (1, 1) => cache.l1_instruction_bytes = size, // CacheInstruction
+                (1, 2) => cache.l1_data_bytes = size,        // CacheData
+                (2, _) => cache.l2_bytes = size,
+                (3, _) => cache.l3_bytes = size,
+                _ => {}
+            }
+        }
+
+        let logical_cores = Self::core_counts().logical;
+
+        vec![PerformanceLevel {
+            id: 0,
+            logical_cores,
+            cache,
+            // Per-cache-list CPU grouping isn't wired up for this backend
+            // yet, so no cache-sharing counts are available to derive
+            // `cache_topology` from.
+            cache_sharing: CacheSharing {
+                cores_per_l2: 0,
+                cores_per_l3: 0,
+            },
+            cache_topology: Vec::new(),
+        }]
+    }
+}
+
+/// Calls `GetLogicalProcessorInformationEx` twice (first to size the
+/// buffer, then to fill it) and returns the raw buffer along with the byte
+/// offset of each variable-length record within it.
+///
+/// Records are deliberately *not* copied out by value:
+/// `PROCESSOR_RELATIONSHIP::GroupMask` is declared as a 1-element trailing
+/// array mirroring its C `ANYSIZE_ARRAY` declaration, with any entries
+/// beyond the first (for `GroupCount > 1`) living past the end of the
+/// struct. Copying a record truncates those entries, so callers must read
+/// through a pointer into this same `buffer` instead (see
+/// [`processor_groups`]).
+fn relationship_records(
+    relationship: LOGICAL_PROCESSOR_RELATIONSHIP,
+) -> (Vec<u8>, Vec<usize>) {
+    let mut len: u32 = 0;
+    // SAFETY: a null buffer with `len == 0` is the documented way to ask
+    // Windows for the required buffer size.
+    unsafe {
+        GetLogicalProcessorInformationEx(relationship, std::ptr::null_mut(), &mut len);
+    }
+    if len == 0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut buffer = vec![0u8; len as usize];
+    // SAFETY: `buffer` is exactly `len` bytes, matching the size Windows
+    // just reported it needs.
+    let ok = unsafe {
+        GetLogicalProcessorInformationEx(
+            relationship,
+            buffer.as_mut_ptr() as *mut _,
+            &mut len,
+        )
+    };
+    if ok == 0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut offsets = Vec::new();
+    let mut offset = 0usize;
+    while offset < buffer.len() {
+        // SAFETY: each record's `Size` field (read from the buffer Windows
+        // just populated) bounds the next record within `buffer`.
+        let record: &SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX =
+            unsafe { &*(buffer.as_ptr().add(offset) as *const _) };
+        offsets.push(offset);
+        offset += record.Size as usize;
+    }
+    (buffer, offsets)
+}
+
+/// Reads a `PROCESSOR_RELATIONSHIP`'s `GroupCount` `GROUP_AFFINITY` entries
+/// directly from `processor`'s address, rather than indexing the 1-element
+/// `GroupMask` field the Rust binding declares (which only holds the first
+/// entry and panics on a bounds-checked slice for `GroupCount > 1`).
+fn processor_groups(processor: &PROCESSOR_RELATIONSHIP) -> &[GROUP_AFFINITY] {
+    let group_mask_ptr = processor.GroupMask.as_ptr();
+    // SAFETY: the kernel allocates `GroupCount` contiguous `GROUP_AFFINITY`
+    // entries starting at `GroupMask`'s address; `processor` (and the
+    // `relationship_records` buffer backing it) outlives this slice.
+    unsafe { std::slice::from_raw_parts(group_mask_ptr, processor.GroupCount as usize) }
+}
+
+/// A `GetSystemTimes` sample (aggregate idle/busy tick counts) plus the
+/// running EMA load-average accumulator.
+///
+/// `GetSystemTimes` only reports system-wide totals, so there is no
+/// per-core breakdown on this backend; [`CpuUsage::per_core`] stays empty.
+pub struct WindowsUsageSnapshot {
+    idle: u64,
+    busy: u64,
+    load_ema: (f64, f64, f64),
+}
+
+fn filetime_to_u64(ft: FILETIME) -> u64 {
+    (u64::from(ft.dwHighDateTime) << 32) | u64::from(ft.dwLowDateTime)
+}
+
+/// Reads the system-wide idle/kernel/user tick counts via `GetSystemTimes`.
+/// Returns `(idle, busy)`, where `busy` is kernel+user time with the idle
+/// time (which `GetSystemTimes` double-counts into the kernel figure)
+/// subtracted back out.
+fn read_system_times() -> (u64, u64) {
+    let mut idle_time: FILETIME = unsafe { mem::zeroed() };
+    let mut kernel_time: FILETIME = unsafe { mem::zeroed() };
+    let mut user_time: FILETIME = unsafe { mem::zeroed() };
+
+    // SAFETY: the three out-params are valid pointers to local `FILETIME`s.
+    let ok = unsafe { GetSystemTimes(&mut idle_time, &mut kernel_time, &mut user_time) };
+    if ok == 0 {
+        return (0, 0);
+    }
+
+    let idle = filetime_to_u64(idle_time);
+    let kernel = filetime_to_u64(kernel_time);
+    let user = filetime_to_u64(user_time);
+    (idle, kernel.saturating_sub(idle) + user)
+}
+
+impl CpuUsageSource for WindowsCpuInfoSource {
+    type Snapshot = WindowsUsageSnapshot;
+
+    fn refresh(previous: &mut Option<Self::Snapshot>) -> CpuUsage {
+        let (idle, busy) = read_system_times();
+
+        let (total, load_ema) = match previous {
+            Some(previous) => {
+                let idle_delta = idle.saturating_sub(previous.idle);
+                let busy_delta = busy.saturating_sub(previous.busy);
+                let total_delta = idle_delta + busy_delta;
+                let instantaneous = if total_delta == 0 {
+                    0.0
+                } else {
+                    busy_delta as f64 / total_delta as f64
+                };
+
+                let (prev_1, prev_5, prev_15) = previous.load_ema;
+                (
+                    (instantaneous * 100.0) as f32,
+                    (
+                        prev_1 * LOAD_EMA_1 + instantaneous * (1.0 - LOAD_EMA_1),
+                        prev_5 * LOAD_EMA_5 + instantaneous * (1.0 - LOAD_EMA_5),
+                        prev_15 * LOAD_EMA_15 + instantaneous * (1.0 - LOAD_EMA_15),
+                    ),
+                )
+            }
+            None => (0.0, (0.0, 0.0, 0.0)),
+        };
+
+        *previous = Some(WindowsUsageSnapshot { idle, busy, load_ema });
+
+        CpuUsage {
+            per_core: Vec::new(),
+            total,
+            load_avg: crate::LoadAverage {
+                one_minute: load_ema.0,
+                five_minute: load_ema.1,
+                fifteen_minute: load_ema.2,
+            },
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use rs_sysctl_typed_cpu::CPUInfo;
+use rs_sysctl_typed_cpu::{CPUInfo, CpuSampler};
 
 #[test]
 fn test_cpu_info_creation() {
@@ -39,6 +39,22 @@ fn test_cpu_info_population() {
         cpu_info.frequency.hz >= 0,
         "Frequency should be a non-negative number"
     );
+    // measured_hz is only populated (and only with the "measure-frequency"
+    // feature enabled) when hz couldn't be determined from the OS.
+    if cpu_info.frequency.hz != 0 {
+        assert!(
+            cpu_info.frequency.measured_hz.is_none(),
+            "measured_hz should stay unset when hz is already known"
+        );
+    }
+
+    // Check CpuFeatures - the raw string should round-trip, and decoding
+    // should never panic regardless of what the machine reports.
+    assert_eq!(
+        cpu_info.identification.features.raw,
+        cpu_info.identification.feature_bits,
+        "Decoded features should keep the raw feature_bits string"
+    );
 
     // If there are performance levels, check their contents
     for level in &cpu_info.performance_levels {
@@ -67,3 +83,27 @@ fn test_cpu_info_population() {
         // Cores per L3 can be 0
     }
 }
+
+#[test]
+fn test_cpu_sampler_refresh() {
+    // This test ensures that CpuSampler can be created and refreshed without
+    // panicking. The first sample has nothing to diff against, so we only
+    // check that usage totals and the load average are non-negative; the
+    // values themselves will vary between machines and backends.
+    let mut sampler = CpuSampler::new();
+    let first = sampler.refresh();
+    assert!(
+        first.total >= 0.0,
+        "Total usage should be a non-negative percentage"
+    );
+
+    let second = sampler.refresh();
+    assert!(
+        second.total >= 0.0,
+        "Total usage should be a non-negative percentage"
+    );
+    assert!(
+        second.load_avg.one_minute >= 0.0,
+        "Load average should be a non-negative number"
+    );
+}